@@ -1,99 +1,207 @@
 //! This module provides utilities for shelling out commands to the OS.
 //!
-//! To have the OS execute a program, use the `exec` function.
+//! To have the OS execute a program, build a `Cmd` with `Cmd::new` and
+//! call its `run` method.
 
-use std::io::ErrorKind;
-use std::process::Command;
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Output, Stdio};
+use std::thread;
 
-/// Explicit errors we handle.
-pub enum Error {
-    NoProg(String),
-    NoFile(String),
-    NoPerm(String),
-    Other(String),
-}
+use crate::error::{Error, Kind};
 
 /// The results of a program execution (a system call).
+///
+/// `stdout`/`stderr` are kept as raw bytes rather than `String`, since a
+/// program rcat shells out to (e.g. `cat` on a binary file) is under no
+/// obligation to produce valid UTF-8.
 pub struct Execution {
-    stdout: String,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    code: i32,
 }
 
 /// Construct an `Execution` instance.
-pub fn make_execution(stdout: String) -> Execution {
+pub fn make_execution(stdout: Vec<u8>, stderr: Vec<u8>, code: i32) -> Execution {
     Execution{
         stdout: stdout,
+        stderr: stderr,
+        code: code,
     }
 }
 
 /// Get the stdout data of an execution.
-pub fn stdout(execution: Execution) -> String {
-    execution.stdout
+pub fn stdout(execution: &Execution) -> Vec<u8> {
+    execution.stdout.clone()
+}
+
+/// Get the stderr data of an execution.
+pub fn stderr(execution: &Execution) -> Vec<u8> {
+    execution.stderr.clone()
+}
+
+/// Get the exit code of an execution.
+pub fn code(execution: &Execution) -> i32 {
+    execution.code
 }
 
-/// Have the OS execute a program with arguments.
+/// A builder for a program to shell out to the OS.
 ///
-/// Returns an `Execution` record if all goes okay,
-/// or an explicit `Error` if something went wrong.
-pub fn exec(prog: String, args: Vec<String>) -> Result<Execution, Error> {
-
-    // We'll need a copy of this below.
-    let p = prog.clone();
-
-    // Run the process and handle the result.
-    let result = Command::new(prog)
-        .args(args)
-        .output();
-    match result {
-
-        // In case the OS raises some errors.
-        Err(err) => {
-            match err.kind() {
-                ErrorKind::NotFound => {
-                    let msg =
-                        format!("No `{}` program found on your machine", p)
-                        .to_string();
-                    Err(Error::NoProg(msg))
-                },
-                ErrorKind::PermissionDenied => {
-                    let msg =
-                        format!("No permission to execute `{}`", p)
-                        .to_string();
-                    Err(Error::NoPerm(msg))
-                },
-                _ => Err(Error::Other(err.to_string())),
-            }
-        },
-        
-        // The process exited safely.
-        Ok(output) => {
-
-            // Did the process succeed? (Exit code of 0.)
-            match output.status.success() {
-
-                // Unpack the stdout.
-                true => {
-                    let data = String::from_utf8(output.stdout).unwrap();
-                    let out = make_execution(data);
-                    Ok(out)
-                },
-
-                // Inspect stderr to find particular errors.
-                false => {
-                    let data = String::from_utf8(output.stderr).unwrap();
-                    if data.contains("No such file") {
-                        let out = Error::NoFile(data);
-                        Err(out)
-                    } else if data.contains("permission denied") {
-                        let out = Error::NoPerm(data);
-                        Err(out)
-                    } else {
-                        let out = Error::Other(data);
-                        Err(out)
-                    }
+/// Construct one with `Cmd::new`, configure it with `args`, `env`,
+/// `cwd`, and/or `stdin_data`, then call `run` to execute it.
+///
+/// # Examples
+///
+/// ```ignore
+/// let execution = Cmd::new("cat".to_string())
+///     .args(vec!["/path/to/file".to_string()])
+///     .env("LC_ALL".to_string(), "C".to_string())
+///     .run()?;
+/// ```
+pub struct Cmd {
+    prog: String,
+    args: Vec<String>,
+    envs: Vec<(String, String)>,
+    cwd: Option<String>,
+    stdin_data: Option<Vec<u8>>,
+}
+
+impl Cmd {
+
+    /// Start building a command that will run `prog`.
+    pub fn new(prog: String) -> Cmd {
+        Cmd {
+            prog: prog,
+            args: Vec::new(),
+            envs: Vec::new(),
+            cwd: None,
+            stdin_data: None,
+        }
+    }
+
+    /// Append arguments to pass to the program.
+    pub fn args(mut self, args: Vec<String>) -> Cmd {
+        self.args.extend(args);
+        self
+    }
+
+    /// Set an environment variable for the program.
+    pub fn env(mut self, key: String, value: String) -> Cmd {
+        self.envs.push((key, value));
+        self
+    }
+
+    /// Set the working directory the program should run in.
+    pub fn cwd(mut self, dir: String) -> Cmd {
+        self.cwd = Some(dir);
+        self
+    }
+
+    /// Provide bytes to write to the program's stdin.
+    ///
+    /// When set, the program is spawned with its stdin piped, and
+    /// `data` is written to it before its output is collected. This is
+    /// how callers forward their own stdin through to the program.
+    pub fn stdin_data(mut self, data: Vec<u8>) -> Cmd {
+        self.stdin_data = Some(data);
+        self
+    }
+
+    /// Have the OS execute the built-up command.
+    ///
+    /// Returns an `Execution` record if the program could be launched,
+    /// whatever its exit status, or an explicit `Error` if the program
+    /// itself could not be started at all, or if writing `stdin_data`
+    /// to it failed. The `Execution`'s stdout, stderr, and exit code
+    /// are what the OS reported, so callers can propagate the real exit
+    /// status of the program they ran; a program killed by a signal has
+    /// no exit code and is recorded with code 1.
+    pub fn run(self) -> Result<Execution, Error> {
+
+        // We'll need a copy of this below.
+        let p = self.prog.clone();
+
+        let mut command = Command::new(self.prog);
+        command.args(self.args);
+        for (key, value) in &self.envs {
+            command.env(key, value);
+        }
+        if let Some(dir) = &self.cwd {
+            command.current_dir(dir);
+        }
+
+        let result = match self.stdin_data {
+            Some(data) => run_with_stdin(command, data),
+            None => command.output(),
+        };
+
+        match result {
+
+            // In case the OS raises some errors.
+            Err(err) => {
+                match err.kind() {
+                    ErrorKind::NotFound => {
+                        let msg =
+                            format!("No `{}` program found on your machine", p)
+                            .to_string();
+                        Err(Error::with_source(Kind::NoProg, msg, err))
+                    },
+                    ErrorKind::PermissionDenied => {
+                        let msg =
+                            format!("No permission to execute `{}`", p)
+                            .to_string();
+                        Err(Error::with_source(Kind::NoPerm, msg, err))
+                    },
+                    _ => {
+                        let msg = err.to_string();
+                        Err(Error::with_source(Kind::Other, msg, err))
+                    },
                 }
+            },
+
+            // The process ran to completion (whether it succeeded or
+            // not). Capture its stdout, stderr, and real exit code
+            // as-is, as raw bytes -- the program isn't obligated to
+            // produce valid UTF-8 (e.g. `cat` on a binary file).
+            Ok(output) => {
+                let code = output.status.code().unwrap_or(1);
+                Ok(make_execution(output.stdout, output.stderr, code))
             }
+
         }
 
     }
 
 }
+
+/// Spawn `command` with its stdin piped, write `data` to it, then
+/// collect its output, just like `Command::output` would.
+///
+/// The write happens on a separate thread, concurrently with
+/// `wait_with_output` reading the child's stdout/stderr. If `data` is
+/// written synchronously before `wait_with_output` is called, a child
+/// that fills its stdout/stderr pipe before it has finished reading
+/// stdin would deadlock: the child blocks writing its output while we
+/// block writing its input.
+fn run_with_stdin(
+    mut command: Command,
+    data: Vec<u8>,
+) -> std::io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Dropping the `stdin` handle at the end of the writer thread closes
+    // it, which signals EOF to the child once we're done writing.
+    let mut stdin = child.stdin.take().expect("stdin was piped");
+    let writer = thread::spawn(move || stdin.write_all(&data));
+
+    let output = child.wait_with_output()?;
+
+    // Propagate a failed write (e.g. the child closed its stdin early),
+    // but only after we've already collected the child's output.
+    writer.join().expect("stdin writer thread panicked")?;
+
+    Ok(output)
+}
@@ -1,32 +1,84 @@
 //! This module is the main handler for the app.
 //!
-//! To run the app, pass a vector of filepaths to the `run` function.
+//! To run the app, pass the recognized flags and a vector of filepaths
+//! to the `run` function.
 
-use crate::app::cmd;
+use std::io::{self, Read};
+
+use crate::app::cmd::{self, Cmd};
+
+/// The exit code used when rcat itself couldn't even launch `cat`, or
+/// couldn't read its own stdin to forward it, since there's no real
+/// process exit status to propagate in either case.
+const INTERNAL_ERR_CODE: i32 = 1;
+
+/// The filepath argument that classically means "read from stdin".
+const STDIN_MARKER: &'static str = "-";
+
+/// The outcome of running the app: what to print on stdout, what to
+/// print on stderr, and the exit code the caller should use. `stdout`
+/// and `stderr` are raw bytes, since `cat` is under no obligation to
+/// produce valid UTF-8.
+pub struct RunResult {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub code: i32,
+}
 
 /// This function runs the main application.
 ///
-/// It takes a vector of filepaths to cat, it asks the OS to `cat` them,
-/// and it returns a resulting message, which is either the result
-/// if everything went well, or an error message.
-pub fn run(filepaths: Vec<String>) -> String {
+/// It takes the `cat` flags rcat recognized and a vector of filepaths
+/// to cat, it asks the OS to `cat` them, and it returns a `RunResult`
+/// with the separated stdout/stderr bytes and the exit code to
+/// propagate, whether that's `cat`'s own exit status or a fixed code
+/// for an internal error (e.g. `cat` couldn't be found at all, or our
+/// own stdin couldn't be read to forward to it).
+pub fn run(flags: Vec<String>, filepaths: Vec<String>) -> RunResult {
 
-    // Have the OS `cat` the `filepaths`. 
-    let prog = "cat".to_string();
-    let args = filepaths.clone();
-    let result = cmd::exec(prog, args);
+    // Have the OS `cat` the `filepaths`, with any recognized flags
+    // passed through first. Use a clean locale so `cat`'s own error
+    // messages are deterministic and easy to match on.
+    let mut args = flags;
+    args.extend(filepaths.clone());
+    let mut cmd = Cmd::new("cat".to_string())
+        .args(args)
+        .env("LC_ALL".to_string(), "C".to_string())
+        .env("LANG".to_string(), "C".to_string());
 
-    // Handle the results.
+    // Classic `cat` treats `-` as "read from stdin". Forward our own
+    // stdin through to the child so that still works. If we can't even
+    // read our own stdin, that's a real failure worth reporting, not
+    // something to paper over by letting the child read from a closed
+    // stdin instead.
+    if filepaths.iter().any(|path| path == STDIN_MARKER) {
+        let mut data = Vec::new();
+        if let Err(err) = io::stdin().read_to_end(&mut data) {
+            return RunResult {
+                stdout: Vec::new(),
+                stderr: format!("Could not read stdin: {}\n", err).into_bytes(),
+                code: INTERNAL_ERR_CODE,
+            };
+        }
+        cmd = cmd.stdin_data(data);
+    }
+
+    let result = cmd.run();
+
+    // Handle the results. An error here represents rcat itself failing
+    // to launch `cat` at all, or failing to write our stdin to it, so
+    // there's no exit status of `cat`'s to propagate; fall back to a
+    // fixed code.
     match result {
-        Err(err) => {
-            match err {
-                cmd::Error::NoProg(msg) => msg,
-                cmd::Error::NoFile(msg) => msg,
-                cmd::Error::NoPerm(msg) => msg,
-                cmd::Error::Other(msg) => msg,
-            }
+        Err(err) => RunResult {
+            stdout: Vec::new(),
+            stderr: err.to_string().into_bytes(),
+            code: INTERNAL_ERR_CODE,
+        },
+        Ok(execution) => RunResult {
+            stdout: cmd::stdout(&execution),
+            stderr: cmd::stderr(&execution),
+            code: cmd::code(&execution),
         },
-        Ok(execution) => cmd::stdout(execution)
     }
 
 }
@@ -2,32 +2,34 @@
 //!
 //! To parse a vector of arguments, use the `parse` function.
 
-use crate::cli::output;
+use std::fs;
 
-/// Explicit errors we handle.
-#[derive(Debug, PartialEq)]
-pub enum Error {
-    Help(String),
-    InvalidOpts(String),
-    NoArgs(String)
-}
+use crate::cli::output;
+use crate::error::{self, Error, Kind};
 
 /// Raw command line args will be parsed into this.
 #[derive(Debug, PartialEq)]
 pub struct Config {
     filepaths: Vec<String>,
+    flags: Vec<String>,
 }
 
 /// Construct a new `Config` instance.
-pub fn make_config(filepaths: Vec<String>) -> Config {
+pub fn make_config(filepaths: Vec<String>, flags: Vec<String>) -> Config {
     Config {
         filepaths: filepaths,
+        flags: flags,
     }
 }
 
 /// Gets the filepaths from config.
-pub fn filepaths(config: Config) -> Vec<String> {
-    config.filepaths
+pub fn filepaths(config: &Config) -> Vec<String> {
+    config.filepaths.clone()
+}
+
+/// Gets the recognized `cat` flags from config.
+pub fn flags(config: &Config) -> Vec<String> {
+    config.flags.clone()
 }
 
 /// Returns the tail of a vector.
@@ -67,85 +69,182 @@ fn contains_help(args: Vec<String>) -> bool {
     args.contains(&"-h".to_string()) || args.contains(&"--help".to_string())
 }
 
+/// Expands any `@path` tokens in a vector of arguments.
+///
+/// Each `@path` token is replaced in place by the lines of the file at
+/// `path`, one argument per line. Both `\n` and `\r\n` line endings are
+/// stripped, and a blank line yields an empty-string argument. Files are
+/// not scanned recursively, so an `@path` token found inside a loaded
+/// file is kept as a literal argument rather than expanded again.
+///
+/// # Examples
+///
+/// ```ignore
+/// let args = vec!["a".to_string(), "@/path/to/args.txt".to_string()];
+/// let expanded = expand_argfiles(args);
+///
+/// // assert_eq!(expanded, Ok(vec!["a".to_string(), "b".to_string(), "c".to_string()]));
+/// ```
+fn expand_argfiles(args: Vec<String>) -> Result<Vec<String>, Error> {
+    let mut expanded = Vec::new();
+    for arg in args {
+        if arg.starts_with("@") {
+            let path = &arg[1..];
+            let contents = fs::read_to_string(path).map_err(|err| {
+                let msg = format!("Could not read args file `{}`: {}", path, err);
+                let kind = error::classify_io_error(&err);
+                Error::with_source(kind, msg, err)
+            })?;
+            for line in contents.lines() {
+                expanded.push(line.to_string());
+            }
+        } else {
+            expanded.push(arg);
+        }
+    }
+    Ok(expanded)
+}
+
+/// The filepath argument that classically means "read from stdin",
+/// not an option, even though it starts with a dash.
+const STDIN_MARKER: &'static str = "-";
+
 /// Finds all unrecognized options in a vector of arguments.
 ///
-/// It filters the arguments down to those that start with a dash,
-/// but which are not `-h` or `--help`.
+/// It filters the arguments down to those that start with a dash, but
+/// which are not `-h`/`--help`, not the `-` stdin marker, and not one
+/// of the options in `output::OPTIONS`.
 ///
 /// # Examples
 ///
 /// ```ignore
-/// let args_1 = vec!["a".to_string(), "-b".to_string(), "-h".to_string()];
+/// let args_1 = vec!["a".to_string(), "-n".to_string(), "-h".to_string()];
 /// let invalid_opts_1 = invalid_opts(args_1);
 ///
 /// let args_2 = vec!["-c".to_string(), "--help".to_string()];
 /// let invalid_opts_2 = invalid_opts(args_2);
 ///
-/// let args_3 = vec!["a".to_string(), "b".to_string()];
+/// let args_3 = vec!["a".to_string(), "b".to_string(), "-".to_string()];
 /// let invalid_opts_3 = invalid_opts(args_3);
 ///
-/// assert_eq!(invalid_opts_1, vec!["-b".to_string()]);
+/// assert_eq!(invalid_opts_1, vec![]);
 /// assert_eq!(invalid_opts_2, vec!["-c".to_string()]);
 /// assert_eq!(invalid_opts_3, vec![]);
 /// ```
 fn invalid_options(args: Vec<String>) -> Vec<String> {
     let mut args_copy = args.clone();
-    args_copy.retain(|x| x.starts_with("-") && x != "-h" && x != "--help");
+    args_copy.retain(|x| {
+        x.starts_with("-")
+            && x != "-h"
+            && x != "--help"
+            && x != STDIN_MARKER
+            && !output::is_recognized_option(x)
+    });
     args_copy.clone()
 }
 
+/// Splits the tail of a parsed argument list into recognized `cat`
+/// flags (translated into the form `cat` expects) and filepaths.
+///
+/// Assumes `args` contains only filepaths, the `-` stdin marker, and
+/// options already known to be recognized (i.e. `invalid_options`
+/// found nothing).
+///
+/// # Examples
+///
+/// ```ignore
+/// let args = vec!["-n".to_string(), "/path/1".to_string(), "-".to_string()];
+/// let (flags, paths) = classify(args);
+///
+/// assert_eq!(flags, vec!["-n".to_string()]);
+/// assert_eq!(paths, vec!["/path/1".to_string(), "-".to_string()]);
+/// ```
+fn classify(args: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let mut flags = Vec::new();
+    let mut filepaths = Vec::new();
+    for arg in args {
+        if arg != STDIN_MARKER && arg.starts_with("-") {
+            flags.push(output::cat_flag_for(&arg));
+        } else {
+            filepaths.push(arg);
+        }
+    }
+    (flags, filepaths)
+}
+
 /// Parses a vector of arguments.
 ///
-/// The arguments are assumed to be a list of filepaths, or `-h` or `--help`.
-/// If `-h` or `--help` are present, an `Err` with usage is returned.
-/// If no arguments are present, an `Err` with a message is returned.
-/// If invalid options are present, an `Err` with a message is returned.
-/// Otherwise, the arguments are returned, as a vector of filepaths.
-/// Note that the first argument in the original list is removed,
-/// since that is the name of the invoked program.
+/// The arguments are assumed to be a list of filepaths and/or the
+/// recognized options in `output::OPTIONS`, or `-h`/`--help`. Before
+/// anything else is classified, any `@path` token in the tail of the
+/// arguments is expanded into the lines of the file it names (see
+/// `expand_argfiles`); an unreadable `@path` is reported as a `NoFile`,
+/// `NoPerm`, or `Other` error, depending on why it couldn't be read.
+/// If `-h` or `--help` are present, an `Err` with
+/// usage is returned. If no arguments are present, an `Err` with a
+/// message is returned. If unrecognized options are present, an `Err`
+/// with a message is returned. Otherwise, a `Config` is returned
+/// holding the filepaths and the recognized options translated into
+/// the flags `cat` expects. Note that the first argument in the
+/// original list is removed, since that is the name of the invoked
+/// program.
 ///
 /// # Examples:
 ///
 /// ```
 /// use rcat::cli::args;
 /// use rcat::cli::output;
+/// use rcat::error::{Error, Kind};
 ///
-/// let arguments = 
+/// let arguments =
 ///     vec!["rcat".to_string(), "/path/1".to_string(), "/path/2".to_string()];
 /// let result = args::parse(arguments);
 ///
-/// let config = 
-///     args::make_config(vec!["/path/1".to_string(), "/path/2".to_string()]);
+/// let config = args::make_config(
+///     vec!["/path/1".to_string(), "/path/2".to_string()],
+///     vec![],
+/// );
 /// let expected = Ok(config);
 /// assert_eq!(result, expected);
 ///
-/// let arguments = vec!["rcat".to_string(), "-h".to_string()]; 
+/// let arguments =
+///     vec!["rcat".to_string(), "-n".to_string(), "/path/1".to_string()];
 /// let result = args::parse(arguments);
 ///
-/// let e = args::Error::Help(output::USAGE.to_string());
+/// let config = args::make_config(
+///     vec!["/path/1".to_string()],
+///     vec!["-n".to_string()],
+/// );
+/// let expected = Ok(config);
+/// assert_eq!(result, expected);
+///
+/// let arguments = vec!["rcat".to_string(), "-h".to_string()];
+/// let result = args::parse(arguments);
+///
+/// let e = Error::new(Kind::Help, output::usage());
 /// let expected = Err(e);
 /// assert_eq!(result, expected);
 ///
-/// let arguments = vec!["rcat".to_string(), "--help".to_string()]; 
+/// let arguments = vec!["rcat".to_string(), "--help".to_string()];
 /// let result = args::parse(arguments);
 ///
-/// let e = args::Error::Help(output::USAGE.to_string());
+/// let e = Error::new(Kind::Help, output::usage());
 /// let expected = Err(e);
 /// assert_eq!(result, expected);
 ///
 /// let arguments = vec!["rcat".to_string()];
 /// let result = args::parse(arguments);
-/// 
-/// let e = args::Error::NoArgs(output::NO_ARGS_ERR.to_string());
+///
+/// let e = Error::new(Kind::NoArgs, output::NO_ARGS_ERR.to_string());
 /// let expected = Err(e);
 /// assert_eq!(result, expected);
 ///
 /// let arguments =
-///     vec!["rcat".to_string(), "/path/1".to_string(), "-e".to_string()]; 
+///     vec!["rcat".to_string(), "/path/1".to_string(), "-e".to_string()];
 /// let result = args::parse(arguments);
 ///
 /// let invalid_opts = vec!["-e".to_string()];
-/// let e = args::Error::InvalidOpts(output::invalid_opts_err(invalid_opts));
+/// let e = Error::new(Kind::InvalidOpts, output::invalid_opts_err(invalid_opts));
 /// let expected = Err(e);
 /// assert_eq!(result, expected);
 /// ```
@@ -153,26 +252,33 @@ pub fn parse(args: Vec<String>) -> Result<Config, Error> {
 
     // If there aren't any arguments, report it.
     if args.len() < 2 {
-        let err = Error::NoArgs(output::NO_ARGS_ERR.to_string());
+        let err = Error::new(Kind::NoArgs, output::NO_ARGS_ERR.to_string());
         return Err(err);
     }
 
+    // Expand any `@path` argument files in the tail before classifying
+    // anything else.
+    let prog = args[0].clone();
+    let expanded_tail = expand_argfiles(tail(args))?;
+    let args = [vec![prog], expanded_tail].concat();
+
     // If help is requested, return the help/usage.
     if contains_help(args.clone()) {
-        let err = Error::Help(output::USAGE.to_string());
+        let err = Error::new(Kind::Help, output::usage());
         return Err(err);
     }
 
     // If there are invalid options, report it.
     let invalid_opts = invalid_options(args.clone());
     if invalid_opts.len() > 0 {
-        let err = Error::InvalidOpts(output::invalid_opts_err(invalid_opts));
+        let err = Error::new(Kind::InvalidOpts, output::invalid_opts_err(invalid_opts));
         return Err(err);
     }
 
-    // If we made it here, we assume the remaining arguments
-    // are okay, and that they are filepaths.
-    let config = make_config(tail(args));
+    // If we made it here, we assume the remaining arguments are okay,
+    // so split them into the recognized flags and the filepaths.
+    let (flags, filepaths) = classify(tail(args));
+    let config = make_config(filepaths, flags);
     Ok(config)
 
 }
@@ -3,7 +3,7 @@
 use std::process;
 
 /// Exit the program with an error.
-pub fn exit_with_err(msg: String) {
+pub fn exit_with_err(msg: String) -> ! {
     eprintln!("Error: {}", msg);
     process::exit(1);
 }
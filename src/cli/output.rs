@@ -1,5 +1,85 @@
 //! Pre-canned messages for output.
 
+/// Describes a single `cat` option that rcat recognizes and forwards.
+pub struct OptionSpec {
+    pub short: &'static str,
+    pub long: &'static str,
+    pub cat_flag: &'static str,
+    pub help: &'static str,
+}
+
+/// The table of `cat` options rcat recognizes and forwards as-is.
+///
+/// This is the single source of truth for both option validation (see
+/// `is_recognized_option`) and the generated `usage` text, so the two
+/// can never drift apart.
+pub const OPTIONS: &'static [OptionSpec] = &[
+    OptionSpec{
+        short: "-n",
+        long: "--number",
+        cat_flag: "-n",
+        help: "Number all output lines.",
+    },
+    OptionSpec{
+        short: "-b",
+        long: "--number-nonblank",
+        cat_flag: "-b",
+        help: "Number nonempty output lines, overrides -n.",
+    },
+    OptionSpec{
+        short: "-s",
+        long: "--squeeze-blank",
+        cat_flag: "-s",
+        help: "Suppress repeated empty output lines.",
+    },
+    OptionSpec{
+        short: "-E",
+        long: "--show-ends",
+        cat_flag: "-E",
+        help: "Display $ at the end of each line.",
+    },
+    OptionSpec{
+        short: "-T",
+        long: "--show-tabs",
+        cat_flag: "-T",
+        help: "Display TAB characters as ^I.",
+    },
+];
+
+/// Checks whether `opt` matches a recognized option in `OPTIONS`.
+///
+/// # Examples
+///
+/// ```
+/// use rcat::cli::output;
+///
+/// assert_eq!(output::is_recognized_option("-n"), true);
+/// assert_eq!(output::is_recognized_option("--show-ends"), true);
+/// assert_eq!(output::is_recognized_option("-e"), false);
+/// ```
+pub fn is_recognized_option(opt: &str) -> bool {
+    OPTIONS.iter().any(|spec| spec.short == opt || spec.long == opt)
+}
+
+/// Translates a recognized option into the flag string `cat` expects.
+///
+/// Panics if `opt` isn't recognized; callers should check
+/// `is_recognized_option` first.
+///
+/// # Examples
+///
+/// ```
+/// use rcat::cli::output;
+///
+/// assert_eq!(output::cat_flag_for("--show-ends"), "-E".to_string());
+/// ```
+pub fn cat_flag_for(opt: &str) -> String {
+    OPTIONS.iter()
+        .find(|spec| spec.short == opt || spec.long == opt)
+        .map(|spec| spec.cat_flag.to_string())
+        .expect("cat_flag_for called with an unrecognized option")
+}
+
 /// Generates a message to use if there are invalid options.
 ///
 /// # Examples
@@ -22,8 +102,18 @@ pub fn invalid_opts_err(opts: Vec<String>) -> String {
 /// A message for if there aren't enough arguments.
 pub const NO_ARGS_ERR : &'static str = "Not enough arguments!";
 
-/// A message for help/usage.
-pub const USAGE : &'static str =
+/// Builds the help/usage message, listing every option in `OPTIONS`
+/// alongside `-h`/`--help` so the text can never drift from what
+/// `parse` actually accepts.
+pub fn usage() -> String {
+    let mut opt_lines = vec!["  -h, --help      Display this help.".to_string()];
+    for spec in OPTIONS {
+        opt_lines.push(
+            format!("  {}, {}  {}", spec.short, spec.long, spec.help)
+        );
+    }
+    let opts = opt_lines.join("\n");
+    format!(
 r#"USAGE: rcat [OPTIONS] [ARGEMNTS]
 
   A simple cat program.
@@ -33,11 +123,14 @@ EXAMPLES:
   rcat /path/to/file1 /path/to/file2 ...
 
 OPTIONS:
-  -h, --help      Display this help.
+{}
 
 ARGUMENTS:
   /path/to/file1  A path to a file.
   /path/to/file2  A path to another file.
   ...             Ditto.
 
-"#;
+"#,
+        opts,
+    )
+}
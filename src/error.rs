@@ -0,0 +1,90 @@
+//! This module provides a single error type used across the crate.
+//!
+//! Every fallible operation in rcat -- parsing arguments, expanding
+//! `@path` files, shelling out to `cat` -- returns this `Error`, so
+//! callers only ever have one error type to match on. Where the error
+//! originated from a lower-level failure (an `io::Error`, say), that
+//! failure is kept as the `source`, so a future `--verbose` mode can
+//! walk the whole chain instead of only seeing a pre-rendered string.
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+
+/// The user-facing classification of an `Error`.
+#[derive(Debug, PartialEq)]
+pub enum Kind {
+    Help,
+    NoArgs,
+    InvalidOpts,
+    NoFile,
+    NoPerm,
+    NoProg,
+    Other,
+}
+
+/// A single error type for the whole crate.
+///
+/// Carries a friendly, user-facing `message` plus an optional `source`
+/// -- the lower-level error (if any) that caused it. `Display` renders
+/// just the friendly message; `source` is preserved for diagnostics.
+#[derive(Debug)]
+pub struct Error {
+    kind: Kind,
+    message: String,
+    source: Option<Box<dyn StdError + 'static>>,
+}
+
+impl Error {
+
+    /// Construct an `Error` with no underlying cause.
+    pub fn new(kind: Kind, message: String) -> Error {
+        Error { kind: kind, message: message, source: None }
+    }
+
+    /// Construct an `Error` that was caused by some lower-level error.
+    pub fn with_source(
+        kind: Kind,
+        message: String,
+        source: impl StdError + 'static,
+    ) -> Error {
+        Error { kind: kind, message: message, source: Some(Box::new(source)) }
+    }
+
+    /// The user-facing classification of this error.
+    pub fn kind(&self) -> &Kind {
+        &self.kind
+    }
+}
+
+impl PartialEq for Error {
+    /// Two errors are equal if they have the same kind and message;
+    /// the underlying `source`, if any, isn't part of their identity.
+    fn eq(&self, other: &Error) -> bool {
+        self.kind == other.kind && self.message == other.message
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source.as_deref()
+    }
+}
+
+/// Classify an `io::Error` into the `Kind` a user would recognize.
+///
+/// Useful wherever rcat opens a file on the user's behalf (e.g. an
+/// `@path` argument file) and needs to report why that failed.
+pub fn classify_io_error(err: &io::Error) -> Kind {
+    match err.kind() {
+        io::ErrorKind::NotFound => Kind::NoFile,
+        io::ErrorKind::PermissionDenied => Kind::NoPerm,
+        _ => Kind::Other,
+    }
+}
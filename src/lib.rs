@@ -2,6 +2,9 @@
 //!
 //! This is the main library crate.
 
+/// This module provides a single error type used across the crate.
+pub mod error;
+
 /// This module handles the CLI.
 pub mod cli {
     pub mod exit;
@@ -14,3 +17,7 @@ pub mod app {
     pub mod cmd;
     pub mod handler;
 }
+
+/// This module provides a golden-output test harness for the binary,
+/// used by the integration tests under `tests/`.
+pub mod testing;
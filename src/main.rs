@@ -3,11 +3,13 @@
 //! This is the main executable crate.
 
 use std::env;
+use std::io::{self, Write};
+use std::process::ExitCode;
 use rcat::cli::{exit, args};
 use rcat::app::handler;
 
 /// The main enry point into the program.
-fn main() {
+fn main() -> ExitCode {
 
     // Get the args provided to the command line.
     let raw_args: Vec<String> = env::args().collect();
@@ -18,17 +20,16 @@ fn main() {
     // If we got an error, exit. Otherwise, pass
     // the results off to the app.
     match result {
-        Err(err) => {
-            match err {
-                args::Error::Help(msg) => exit::exit_with_err(msg),
-                args::Error::NoArgs(msg) => exit::exit_with_err(msg),
-                args::Error::InvalidOpts(msg) => exit::exit_with_err(msg),
-            }
-        }
+        Err(err) => exit::exit_with_err(err.to_string()),
         Ok(config) => {
-            let filepaths = args::filepaths(config);
-            let result = handler::run(filepaths);
-            print!("{}", result)
+            let filepaths = args::filepaths(&config);
+            let flags = args::flags(&config);
+            let result = handler::run(flags, filepaths);
+            // Write raw bytes rather than `print!`/`eprint!`, since
+            // `cat`'s output isn't guaranteed to be valid UTF-8.
+            let _ = io::stderr().write_all(&result.stderr);
+            let _ = io::stdout().write_all(&result.stdout);
+            ExitCode::from(result.code as u8)
         },
     }
 
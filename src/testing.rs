@@ -0,0 +1,400 @@
+//! A small golden-output test harness for running the built `rcat`
+//! binary end-to-end and diffing its output against checked-in
+//! expectations, in the style of `ui_test`.
+//!
+//! Use `discover_cases` to find case directories under a root, and
+//! `run_case` to execute one and compare it against what's checked in.
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// A filter used to normalize a volatile fragment of output before
+/// comparing it against the expected golden text (an absolute path, a
+/// locale-dependent phrasing, the program name `cat` embeds in its own
+/// error messages, and so on).
+pub enum Match {
+    /// Replace every exact occurrence of this substring.
+    Exact(String),
+    /// Replace every match of this (minimal) regex. See `find_match`
+    /// for exactly what's supported.
+    Regex(String),
+}
+
+/// What a filter's matches are replaced with.
+const PLACEHOLDER: &'static str = "[..]";
+
+impl Match {
+    /// Replace every match of this filter in `text` with a fixed
+    /// placeholder.
+    fn normalize(&self, text: &str) -> String {
+        match self {
+            Match::Exact(needle) => text.replace(needle.as_str(), PLACEHOLDER),
+            Match::Regex(pattern) => replace_regex(pattern, text, PLACEHOLDER),
+        }
+    }
+}
+
+/// Apply a list of filters to `text`, in order.
+///
+/// # Examples
+///
+/// ```
+/// use rcat::testing::{apply_filters, Match};
+///
+/// // `exact:` replaces a literal substring wherever it occurs.
+/// let filters = vec![Match::Exact("/tmp/abc123".to_string())];
+/// assert_eq!(apply_filters(&filters, "path: /tmp/abc123/file"), "path: [..]/file");
+///
+/// // `regex:` supports literals, `.`, and `*`/`+` repetition.
+/// let filters = vec![Match::Regex("a.c".to_string())];
+/// assert_eq!(apply_filters(&filters, "xabcx"), "x[..]x");
+///
+/// let filters = vec![Match::Regex("a+".to_string())];
+/// assert_eq!(apply_filters(&filters, "xaaabx"), "x[..]bx");
+///
+/// let filters = vec![Match::Regex("za*".to_string())];
+/// assert_eq!(apply_filters(&filters, "z"), "[..]");
+///
+/// // `^` anchors to the true start of the text, not to wherever a
+/// // prior match left off.
+/// let filters = vec![Match::Regex("^a".to_string())];
+/// assert_eq!(apply_filters(&filters, "aa"), "[..]a");
+///
+/// // `$` anchors to the end of the text.
+/// let filters = vec![Match::Regex("x$".to_string())];
+/// assert_eq!(apply_filters(&filters, "xyx"), "xy[..]");
+///
+/// // A zero-width match (e.g. `*` matching nothing) still makes
+/// // progress instead of looping forever.
+/// let filters = vec![Match::Regex("z*".to_string())];
+/// assert_eq!(apply_filters(&filters, "ab"), "[..]a[..]b[..]");
+/// ```
+pub fn apply_filters(filters: &[Match], text: &str) -> String {
+    let mut out = text.to_string();
+    for filter in filters {
+        out = filter.normalize(&out);
+    }
+    out
+}
+
+/// One golden-output test case: the argv to invoke `rcat` with, the
+/// bytes (if any) to write to its stdin, and the expected stdout,
+/// stderr, and exit code, after `filters` have been applied to the
+/// actual output.
+pub struct Case {
+    pub name: String,
+    pub argv: Vec<String>,
+    pub stdin: Vec<u8>,
+    pub expected_stdout: String,
+    pub expected_stderr: String,
+    pub expected_code: i32,
+    pub filters: Vec<Match>,
+}
+
+/// The outcome of running a `Case`.
+pub enum Outcome {
+    Match,
+    Mismatch(String),
+}
+
+/// Discover case directories under `root`.
+///
+/// Each subdirectory of `root` is one case, laid out as:
+///
+///   <case>/argv     one argument per line
+///   <case>/stdin    optional: raw bytes to write to the child's stdin
+///   <case>/stdout   expected stdout, after filters are applied
+///   <case>/stderr   expected stderr, after filters are applied
+///   <case>/code     expected exit code (defaults to 0 if absent)
+///   <case>/filters  optional: one filter per line, `exact:...` or `regex:...`
+pub fn discover_cases(root: &Path) -> Vec<Case> {
+    let mut cases = Vec::new();
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(_) => return cases,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            cases.push(load_case(&path));
+        }
+    }
+    cases.sort_by(|a, b| a.name.cmp(&b.name));
+    cases
+}
+
+/// Load a single case from its directory.
+fn load_case(dir: &Path) -> Case {
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let argv = read_lines(&dir.join("argv"));
+    let stdin = fs::read(dir.join("stdin")).unwrap_or_default();
+    let expected_stdout = fs::read_to_string(dir.join("stdout")).unwrap_or_default();
+    let expected_stderr = fs::read_to_string(dir.join("stderr")).unwrap_or_default();
+    let expected_code = fs::read_to_string(dir.join("code"))
+        .ok()
+        .and_then(|text| text.trim().parse::<i32>().ok())
+        .unwrap_or(0);
+    let filters = read_lines(&dir.join("filters"))
+        .into_iter()
+        .filter_map(|line| parse_filter(&line))
+        .collect();
+    Case { name, argv, stdin, expected_stdout, expected_stderr, expected_code, filters }
+}
+
+/// Read `path` as UTF-8 and split it into lines, or an empty vector if
+/// `path` doesn't exist.
+fn read_lines(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Parse one line of a `filters` file into a `Match`.
+fn parse_filter(line: &str) -> Option<Match> {
+    if let Some(rest) = line.strip_prefix("exact:") {
+        Some(Match::Exact(rest.to_string()))
+    } else if let Some(rest) = line.strip_prefix("regex:") {
+        Some(Match::Regex(rest.to_string()))
+    } else {
+        None
+    }
+}
+
+/// Run a case against the built `rcat` binary at `bin`, with its
+/// current directory set to `cwd` (so cases can reference checked-in
+/// fixture files by relative path), applying the case's filters to the
+/// actual output before comparing it against the checked-in
+/// expectations. The case's `stdin` bytes, if any, are piped to the
+/// child.
+pub fn run_case(bin: &Path, case: &Case, cwd: &Path) -> Outcome {
+    let output = if case.stdin.is_empty() {
+        Command::new(bin)
+            .args(&case.argv)
+            .current_dir(cwd)
+            .output()
+            .unwrap_or_else(|err| panic!("could not run `{}`: {}", bin.display(), err))
+    } else {
+        run_with_stdin(bin, case, cwd)
+    };
+
+    let raw_stdout = String::from_utf8_lossy(&output.stdout);
+    let raw_stderr = String::from_utf8_lossy(&output.stderr);
+    let actual_stdout = apply_filters(&case.filters, &raw_stdout);
+    let actual_stderr = apply_filters(&case.filters, &raw_stderr);
+    let actual_code = output.status.code().unwrap_or(1);
+
+    let mut problems = Vec::new();
+    if actual_stdout != case.expected_stdout {
+        problems.push(line_diff("stdout", &case.expected_stdout, &actual_stdout));
+    }
+    if actual_stderr != case.expected_stderr {
+        problems.push(line_diff("stderr", &case.expected_stderr, &actual_stderr));
+    }
+    if actual_code != case.expected_code {
+        problems.push(format!(
+            "code: expected {}, got {}",
+            case.expected_code, actual_code,
+        ));
+    }
+
+    if problems.is_empty() {
+        Outcome::Match
+    } else {
+        Outcome::Mismatch(problems.join("\n"))
+    }
+}
+
+/// Spawn `bin` for `case` with its stdin piped and `case.stdin` written
+/// to it, then collect its output. The write happens on a separate
+/// thread, concurrently with reading the child's stdout/stderr, so a
+/// case with more stdin than fits in the OS pipe buffer can't deadlock
+/// against the child filling its own output pipe first.
+fn run_with_stdin(bin: &Path, case: &Case, cwd: &Path) -> std::process::Output {
+    let mut child = Command::new(bin)
+        .args(&case.argv)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap_or_else(|err| panic!("could not run `{}`: {}", bin.display(), err));
+
+    let mut stdin = child.stdin.take().expect("stdin was piped");
+    let data = case.stdin.clone();
+    let writer = thread::spawn(move || stdin.write_all(&data));
+
+    let output = child
+        .wait_with_output()
+        .unwrap_or_else(|err| panic!("could not wait on `{}`: {}", bin.display(), err));
+    writer
+        .join()
+        .expect("stdin writer thread panicked")
+        .unwrap_or_else(|err| panic!("could not write stdin for `{}`: {}", bin.display(), err));
+
+    output
+}
+
+/// Render a readable line-by-line diff between `expected` and `actual`.
+fn line_diff(label: &str, expected: &str, actual: &str) -> String {
+    let mut out = format!("{} mismatch:\n", label);
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    let max = expected_lines.len().max(actual_lines.len());
+    for i in 0..max {
+        let expected_line = expected_lines.get(i).copied().unwrap_or("<missing>");
+        let actual_line = actual_lines.get(i).copied().unwrap_or("<missing>");
+        if expected_line != actual_line {
+            out.push_str(&format!(
+                "  line {}:\n    expected: {}\n    actual:   {}\n",
+                i + 1, expected_line, actual_line,
+            ));
+        }
+    }
+    out
+}
+
+/// Replace every non-overlapping match of `pattern` in `text` with
+/// `replacement`.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(replace_regex("a.c", "xabcx", "Y"), "xYx");
+/// assert_eq!(replace_regex("a+", "xaaabx", "Y"), "xYbx");
+/// assert_eq!(replace_regex("^a", "aa", "Y"), "Ya");
+/// ```
+fn replace_regex(pattern: &str, text: &str, replacement: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut pos = 0;
+    while pos <= chars.len() {
+        match find_match(pattern, &chars[pos..], pos == 0) {
+            Some((start, end)) => {
+                let abs_start = pos + start;
+                let abs_end = pos + end;
+                out.push_str(&chars[pos..abs_start].iter().collect::<String>());
+                out.push_str(replacement);
+                if abs_end > abs_start {
+                    pos = abs_end;
+                } else if abs_start < chars.len() {
+                    // A zero-width match: keep the character under it
+                    // so we make progress instead of looping forever.
+                    out.push(chars[abs_start]);
+                    pos = abs_start + 1;
+                } else {
+                    break;
+                }
+            },
+            None => {
+                out.push_str(&chars[pos..].iter().collect::<String>());
+                break;
+            },
+        }
+    }
+    out
+}
+
+/// Find the leftmost match of `pattern` in `text`, returning its
+/// `(start, end)` character indices.
+///
+/// `text` may be a suffix of the text the caller is really searching
+/// (as `replace_regex` does when resuming after a match); `at_start`
+/// says whether `text[0]` is the true start of that original text, so
+/// a `^`-anchored pattern only ever matches once, at the real
+/// beginning, rather than re-anchoring wherever the search resumed.
+///
+/// This is a minimal regex subset -- just enough to normalize the
+/// volatile fragments golden tests need to filter -- supporting
+/// literal characters, `.` (any character), `*`/`+` (repetition of
+/// the preceding atom), and the `^`/`$` anchors.
+///
+/// # Examples
+///
+/// ```ignore
+/// let chars: Vec<char> = "xabcx".chars().collect();
+/// assert_eq!(find_match("a.c", &chars, true), Some((1, 4)));
+/// assert_eq!(find_match("^a", &chars[1..], false), None);
+/// ```
+fn find_match(pattern: &str, text: &[char], at_start: bool) -> Option<(usize, usize)> {
+    let anchored_start = pattern.starts_with('^');
+    if anchored_start && !at_start {
+        return None;
+    }
+    let body = if anchored_start { &pattern[1..] } else { pattern };
+    let pat: Vec<char> = body.chars().collect();
+
+    let last_start = if anchored_start { 0 } else { text.len() };
+    for start in 0..=last_start {
+        if let Some(len) = match_here(&pat, &text[start..]) {
+            return Some((start, start + len));
+        }
+    }
+    None
+}
+
+/// Match `pat` against a prefix of `text`, returning how many
+/// characters of `text` it consumed.
+///
+/// # Examples
+///
+/// ```ignore
+/// let chars: Vec<char> = "abc".chars().collect();
+/// assert_eq!(match_here(&['a', 'b'], &chars), Some(2));
+/// assert_eq!(match_here(&['$'], &[]), Some(0));
+/// assert_eq!(match_here(&['$'], &chars), None);
+/// ```
+fn match_here(pat: &[char], text: &[char]) -> Option<usize> {
+    if pat.is_empty() {
+        return Some(0);
+    }
+    if pat.len() == 1 && pat[0] == '$' {
+        return if text.is_empty() { Some(0) } else { None };
+    }
+    if pat.len() >= 2 && (pat[1] == '*' || pat[1] == '+') {
+        return match_repeat(pat[0], pat[1] == '+', &pat[2..], text);
+    }
+    if !text.is_empty() && (pat[0] == '.' || pat[0] == text[0]) {
+        return match_here(&pat[1..], &text[1..]).map(|n| n + 1);
+    }
+    None
+}
+
+/// Match `atom` repeated (greedily, then backtracking) followed by
+/// `rest`, against a prefix of `text`.
+///
+/// # Examples
+///
+/// ```ignore
+/// let chars: Vec<char> = "aaab".chars().collect();
+/// assert_eq!(match_repeat('a', true, &['b'], &chars), Some(4));
+/// assert_eq!(match_repeat('a', false, &['b'], &"b".chars().collect::<Vec<_>>()), Some(1));
+/// assert_eq!(match_repeat('a', true, &[], &"b".chars().collect::<Vec<_>>()), None);
+/// ```
+fn match_repeat(atom: char, plus: bool, rest: &[char], text: &[char]) -> Option<usize> {
+    let mut max = 0;
+    while max < text.len() && (atom == '.' || text[max] == atom) {
+        max += 1;
+    }
+    let min = if plus { 1 } else { 0 };
+    if max < min {
+        return None;
+    }
+    let mut consumed = max;
+    loop {
+        if let Some(n) = match_here(rest, &text[consumed..]) {
+            return Some(consumed + n);
+        }
+        if consumed == min {
+            return None;
+        }
+        consumed -= 1;
+    }
+}
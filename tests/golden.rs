@@ -0,0 +1,31 @@
+//! Golden-output integration tests for the `rcat` binary.
+//!
+//! Each case directory under `tests/golden/cases` provides the argv to
+//! invoke `rcat` with and the expected stdout, stderr, and exit code,
+//! run with its current directory set to `tests/golden/fixtures` so
+//! cases can reference checked-in fixture files by relative path. See
+//! `rcat::testing` for the case format and comparison logic.
+
+use std::path::{Path, PathBuf};
+
+use rcat::testing::{discover_cases, run_case, Outcome};
+
+#[test]
+fn golden_cases_match() {
+    let golden_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let cases_dir = golden_dir.join("cases");
+    let fixtures_dir = golden_dir.join("fixtures");
+    let bin = PathBuf::from(env!("CARGO_BIN_EXE_rcat"));
+
+    let cases = discover_cases(&cases_dir);
+    assert!(!cases.is_empty(), "no golden cases found under {}", cases_dir.display());
+
+    let mut failures = Vec::new();
+    for case in &cases {
+        if let Outcome::Mismatch(diff) = run_case(&bin, case, &fixtures_dir) {
+            failures.push(format!("case `{}`:\n{}", case.name, diff));
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n\n"));
+}